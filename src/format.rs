@@ -0,0 +1,145 @@
+//! Machine-readable forms of [`M`], complementing the human-readable
+//! `fmt::Display` impl: a Graphviz export for visualizing an automaton
+//! (handy for eyeballing a compiled regex), and a flat table format that
+//! round-trips through `to_table`/`from_table` so automata can be cached
+//! to disk instead of recompiled every run.
+
+use std::collections::BTreeSet;
+
+use crate::M;
+
+fn escape_dot_label(c: char) -> String {
+    match c {
+        '"' => "\\\"".to_string(),
+        '\\' => "\\\\".to_string(),
+        '\n' => "\\n".to_string(),
+        c => c.to_string(),
+    }
+}
+
+impl M {
+    /// Renders `self` as a Graphviz `digraph`: one node per state (double
+    /// circle for accepting states, filled for states in the current
+    /// active set), one labeled edge per `(state, char) -> next` entry.
+    pub fn to_dot(&self) -> String {
+        let mut states: BTreeSet<usize> = BTreeSet::new();
+        states.insert(0);
+        for &(from, _) in self.delta.keys() {
+            states.insert(from);
+        }
+        for targets in self.delta.values() {
+            states.extend(targets.iter().cloned());
+        }
+        states.extend(self.f.iter().cloned());
+
+        let mut out = String::new();
+        out.push_str("digraph M {\n");
+        out.push_str("    rankdir=LR;\n");
+
+        for &s in &states {
+            let shape = if self.f.contains(&s) { "doublecircle" } else { "circle" };
+            let fill = if self.state.contains(&s) { ", style=filled, fillcolor=lightgray" } else { "" };
+            out.push_str(&format!("    {} [shape={}{}];\n", s, shape, fill));
+        }
+
+        for (&(from, c), targets) in &self.delta {
+            for &to in targets {
+                out.push_str(&format!("    {} -> {} [label=\"{}\"];\n", from, to, escape_dot_label(c)));
+            }
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Serializes `self` as a flat `(state, char, next_state)` table plus
+    /// the final-state list, readable back with [`M::from_table`].
+    pub fn to_table(&self) -> String {
+        let mut out = String::new();
+        out.push_str("DELTA\n");
+        for (&(state, c), targets) in &self.delta {
+            for &next_state in targets {
+                out.push_str(&format!("{},{},{}\n", state, c as u32, next_state));
+            }
+        }
+
+        out.push_str("FINAL\n");
+        for &f in &self.f {
+            out.push_str(&format!("{}\n", f));
+        }
+
+        out
+    }
+
+    /// Rebuilds an automaton from text produced by [`M::to_table`].
+    pub fn from_table(text: &str) -> M {
+        let mut flat_delta: Vec<(usize, char, usize)> = vec![];
+        let mut flat_f: Vec<usize> = vec![];
+        let mut in_final = false;
+
+        for raw_line in text.lines() {
+            let line = raw_line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            match line {
+                "DELTA" => in_final = false,
+                "FINAL" => in_final = true,
+                _ if in_final => {
+                    if let Ok(state) = line.parse() {
+                        flat_f.push(state);
+                    }
+                }
+                _ => {
+                    let fields: Vec<&str> = line.split(',').collect();
+                    if let [state, codepoint, next_state] = fields[..] {
+                        if let (Ok(state), Ok(codepoint), Ok(next_state)) =
+                            (state.parse(), codepoint.parse::<u32>(), next_state.parse())
+                        {
+                            if let Some(c) = char::from_u32(codepoint) {
+                                flat_delta.push((state, c, next_state));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        M::new(&flat_delta, &flat_f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_dot_marks_accepting_and_active_states() {
+        let mut m = M::new(&[(0, 'a', 0), (0, 'b', 1)], &[1]);
+        m.next('b');
+
+        let dot = m.to_dot();
+        assert!(dot.starts_with("digraph M {"));
+        assert!(dot.contains("1 [shape=doublecircle, style=filled, fillcolor=lightgray];"));
+        assert!(dot.contains("0 [shape=circle];"));
+        assert!(dot.contains("0 -> 1 [label=\"b\"];"));
+    }
+
+    #[test]
+    fn table_round_trips_through_to_table_and_from_table() {
+        let original = M::new(&[(0, 'a', 0), (0, 'b', 1), (1, 'a', 1)], &[1]);
+        let mut restored = M::from_table(&original.to_table());
+
+        for s in ["b", "ab", "aaab", "a", ""] {
+            let mut fresh = M::new(&[(0, 'a', 0), (0, 'b', 1), (1, 'a', 1)], &[1]);
+            for c in s.chars() {
+                fresh.next(c);
+                restored.next(c);
+            }
+            assert_eq!(fresh.is_accepted(), restored.is_accepted(), "mismatch on {:?}", s);
+            fresh.reset();
+            restored.reset();
+        }
+    }
+}
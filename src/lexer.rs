@@ -0,0 +1,220 @@
+//! A first-class lexer built on top of [`M`]: a bank of prioritized rules
+//! run side by side over the input, picking the longest match (maximal
+//! munch) and breaking ties in favor of whichever rule was declared first.
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::M;
+
+/// A 1-indexed line/column position in the source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A lexical token: the rule that matched (`kind`), the text it matched
+/// (`lexeme`), its byte range in the source (`span`), and where it starts
+/// (`pos`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token<K> {
+    pub kind: K,
+    pub lexeme: String,
+    pub span: Range<usize>,
+    pub pos: Position,
+}
+
+/// No rule matched starting at `offset` (a byte offset into the original
+/// input).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexError {
+    pub offset: usize,
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no rule matched at offset {}", self.offset)
+    }
+}
+
+/// A bank of `(kind, automaton)` rules, tried in declaration order.
+///
+/// `tokenize` runs every rule forward in lockstep, always preferring the
+/// longest lexeme any rule accepts; if several rules accept at that same
+/// length, the one declared earliest (lowest index in `rules`) wins, so a
+/// keyword rule like `IF` should be listed before a catch-all `ID` rule.
+pub struct Lexer<K> {
+    rules: Vec<(K, M)>,
+}
+
+impl<K: Clone> Lexer<K> {
+    pub fn new(rules: Vec<(K, M)>) -> Lexer<K> {
+        Lexer { rules }
+    }
+
+    /// Splits `input` into tokens via maximal munch, or reports the byte
+    /// offset of the first position no rule could match.
+    ///
+    /// Each token's `span` is a byte range into `input`, and `pos` is the
+    /// line/column of its first character (lines are counted by `\n`).
+    pub fn tokenize(&mut self, input: &str) -> Result<Vec<Token<K>>, LexError> {
+        let indices: Vec<(usize, char)> = input.char_indices().collect();
+        let chars: Vec<char> = indices.iter().map(|&(_, c)| c).collect();
+        let mut byte_offsets: Vec<usize> = indices.iter().map(|&(i, _)| i).collect();
+        byte_offsets.push(input.len());
+
+        let mut tokens = vec![];
+        let mut pos = 0;
+        let mut line = 1;
+        let mut column = 1;
+
+        while pos < chars.len() {
+            for (_, m) in self.rules.iter_mut() {
+                m.reset();
+            }
+
+            let mut best: Option<(usize, usize)> = None;
+            let mut i = pos;
+            loop {
+                if i >= chars.len() {
+                    break;
+                }
+
+                let c = chars[i];
+                let mut any_alive = false;
+                for (_, m) in self.rules.iter_mut() {
+                    if !m.is_trapped() {
+                        m.next(c);
+                        any_alive = any_alive || !m.is_trapped();
+                    }
+                }
+                i += 1;
+
+                if let Some(rule) = self.rules.iter().position(|(_, m)| m.is_accepted()) {
+                    best = Some((i, rule));
+                }
+
+                if !any_alive {
+                    break;
+                }
+            }
+
+            match best {
+                Some((end, rule)) => {
+                    let lexeme: String = chars[pos..end].iter().collect();
+                    let kind = self.rules[rule].0.clone();
+                    let span = byte_offsets[pos]..byte_offsets[end];
+                    let token_pos = Position { line, column };
+
+                    for &c in &chars[pos..end] {
+                        if c == '\n' {
+                            line += 1;
+                            column = 1;
+                        } else {
+                            column += 1;
+                        }
+                    }
+
+                    tokens.push(Token { kind, lexeme, span, pos: token_pos });
+                    pos = end;
+                }
+                None => return Err(LexError { offset: byte_offsets[pos] }),
+            }
+        }
+
+        Ok(tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alphabetic_automata() -> M {
+        let lowercase = "abcdefghijklmnopqrstuvwxyz";
+        let delta: Vec<_> = lowercase.chars().flat_map(|c| vec![(0, c, 1), (1, c, 1)]).collect();
+        M::new(delta.as_slice(), &[1])
+    }
+
+    fn numeric_automata() -> M {
+        let numbers = "0123456789";
+        let delta: Vec<_> = numbers.chars().flat_map(|c| vec![(0, c, 1), (1, c, 1)]).collect();
+        M::new(delta.as_slice(), &[1])
+    }
+
+    #[test]
+    fn keyword_beats_generic_identifier_on_a_tie() {
+        let if_rule = M::new(&[(0, 'i', 1), (1, 'f', 2)], &[2]);
+        let id_rule = alphabetic_automata();
+
+        let mut lexer = Lexer::new(vec![("IF", if_rule), ("ID", id_rule)]);
+
+        let tokens = lexer.tokenize("if").unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].kind, "IF");
+        assert_eq!(tokens[0].lexeme, "if");
+        assert_eq!(tokens[0].span, 0..2);
+        assert_eq!(tokens[0].pos, Position { line: 1, column: 1 });
+    }
+
+    #[test]
+    fn maximal_munch_picks_the_longest_lexeme() {
+        let if_rule = M::new(&[(0, 'i', 1), (1, 'f', 2)], &[2]);
+        let id_rule = alphabetic_automata();
+
+        let mut lexer = Lexer::new(vec![("IF", if_rule), ("ID", id_rule)]);
+
+        let tokens = lexer.tokenize("ifa").unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].kind, "ID");
+        assert_eq!(tokens[0].lexeme, "ifa");
+    }
+
+    #[test]
+    fn tokenizes_a_small_expression() {
+        let rules = vec![
+            ("ID", alphabetic_automata()),
+            ("NUMBER", numeric_automata()),
+            ("PAROPEN", M::new(&[(0, '(', 1)], &[1])),
+            ("PARCLOSE", M::new(&[(0, ')', 1)], &[1])),
+            ("SPACE", M::new(&[(0, ' ', 1)], &[1])),
+            ("OPREL", M::new(&[(0, '>', 1)], &[1])),
+        ];
+        let mut lexer = Lexer::new(rules);
+
+        let tokens = lexer.tokenize("(foo 123)").unwrap();
+        let kinds: Vec<_> = tokens.iter().map(|t| t.kind).collect();
+        let lexemes: Vec<_> = tokens.iter().map(|t| t.lexeme.as_str()).collect();
+        let spans: Vec<_> = tokens.iter().map(|t| t.span.clone()).collect();
+        assert_eq!(kinds, vec!["PAROPEN", "ID", "SPACE", "NUMBER", "PARCLOSE"]);
+        assert_eq!(lexemes, vec!["(", "foo", " ", "123", ")"]);
+        assert_eq!(spans, vec![0..1, 1..4, 4..5, 5..8, 8..9]);
+    }
+
+    #[test]
+    fn tracks_line_and_column_across_newlines() {
+        let rules = vec![
+            ("ID", alphabetic_automata()),
+            ("NEWLINE", M::new(&[(0, '\n', 1)], &[1])),
+        ];
+        let mut lexer = Lexer::new(rules);
+
+        let tokens = lexer.tokenize("foo\nbar").unwrap();
+        assert_eq!(
+            tokens.iter().map(|t| t.pos).collect::<Vec<_>>(),
+            vec![
+                Position { line: 1, column: 1 },
+                Position { line: 1, column: 4 },
+                Position { line: 2, column: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_the_offset_of_an_unmatched_character() {
+        let mut lexer = Lexer::new(vec![("ID", alphabetic_automata())]);
+        let err = lexer.tokenize("abc 123").unwrap_err();
+        assert_eq!(err, LexError { offset: 3 });
+    }
+}
@@ -2,19 +2,24 @@
 use std::collections::{BTreeSet, BTreeMap};
 use std::fmt;
 
+mod regex;
+mod lexer;
+mod minimize;
+mod spec;
+mod format;
+
+pub use lexer::{Lexer, LexError, Token};
 
-#[derive(Debug, Clone, Copy)]
-enum MaybeState {
-    State(usize),
-    Trap,
-}
 
 #[derive(Debug)]
 pub struct M {
     delta: BTreeMap<(usize, char), Vec<usize>>,
     f: BTreeSet<usize>,
-    state: MaybeState,
-    previous_states: Vec<MaybeState>,
+    // The set of currently active states. A DFA just happens to keep this
+    // set at size 0 or 1 at all times; an NFA built with several targets
+    // per (state, char) entry can keep several states active at once.
+    state: BTreeSet<usize>,
+    previous_states: Vec<BTreeSet<usize>>,
 }
 
 
@@ -34,26 +39,22 @@ impl M {
         M {
             delta: delta,
             f: f,
-            state: MaybeState::State(0),
+            state: vec![0].into_iter().collect(),
             previous_states: vec![],
         }
     }
 
 
     pub fn next(&mut self, input: char) -> bool {
-        self.previous_states.push(self.state);
-
-        if let MaybeState::State(state) = self.state {
-            match self.delta.get(&(state, input)) {
-                Some(next_states) => {
-                    assert_eq!(next_states.len(), 1, "Expected a single next state (DFA), but found {:?}", next_states);
-                    self.state = MaybeState::State(next_states[0]);
-                },
-                None => {
-                    self.state = MaybeState::Trap;
-                },
+        self.previous_states.push(self.state.clone());
+
+        let mut next_state = BTreeSet::new();
+        for &state in &self.state {
+            if let Some(next_states) = self.delta.get(&(state, input)) {
+                next_state.extend(next_states.iter().cloned());
             }
         }
+        self.state = next_state;
 
         self.is_accepted()
     }
@@ -65,21 +66,15 @@ impl M {
     }
 
     pub fn is_accepted(&self) -> bool {
-        return match self.state {
-            MaybeState::Trap => false,
-            MaybeState::State(state) => self.f.contains(&state),
-        }
+        !self.state.is_disjoint(&self.f)
     }
 
     pub fn is_trapped(&self) -> bool {
-        match self.state {
-            MaybeState::Trap => true,
-            _ => false,
-        }
+        self.state.is_empty()
     }
 
     pub fn reset(&mut self) {
-        self.state = MaybeState::State(0);
+        self.state = vec![0].into_iter().collect();
         self.previous_states = vec![];
     }
 
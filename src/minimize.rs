@@ -0,0 +1,165 @@
+//! DFA minimization via Hopcroft's partition-refinement algorithm, so
+//! automata compiled from a regex (which tend to carry a lot of redundant
+//! states) can be collapsed back down to their canonical size.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::M;
+
+impl M {
+    /// Returns an equivalent automaton with the minimum possible number of
+    /// states, found via Hopcroft's algorithm.
+    ///
+    /// `self` is treated as a (possibly partial) DFA: every `(state, char)`
+    /// entry in `delta` is expected to have a single target, and a missing
+    /// entry is an implicit transition to a sink/trap state. That trap is
+    /// folded into the refinement as an extra state so dead states collapse
+    /// into it. States not reachable from state 0 are dropped up front.
+    pub fn minimize(&self) -> M {
+        let alphabet: BTreeSet<char> = self.delta.keys().map(|&(_, c)| c).collect();
+
+        let mut states: BTreeSet<usize> = BTreeSet::new();
+        let mut frontier = vec![0];
+        while let Some(s) = frontier.pop() {
+            if states.insert(s) {
+                for (_, targets) in self.delta.range((s, char::MIN)..=(s, char::MAX)) {
+                    frontier.extend(targets.iter().cloned());
+                }
+            }
+        }
+
+        // `None` stands for the implicit trap/sink state.
+        let step = |state: Option<usize>, c: char| -> Option<usize> {
+            let state = state?;
+            self.delta.get(&(state, c)).map(|targets| {
+                debug_assert_eq!(targets.len(), 1, "minimize expects a deterministic M");
+                targets[0]
+            })
+        };
+
+        let universe: BTreeSet<Option<usize>> =
+            states.iter().cloned().map(Some).chain(std::iter::once(None)).collect();
+        let accepting: BTreeSet<Option<usize>> =
+            states.iter().filter(|s| self.f.contains(s)).cloned().map(Some).collect();
+        let non_accepting: BTreeSet<Option<usize>> = universe.difference(&accepting).cloned().collect();
+
+        let mut next_block_id = 0;
+        let mut blocks: BTreeMap<usize, BTreeSet<Option<usize>>> = BTreeMap::new();
+        for block in [accepting, non_accepting] {
+            if !block.is_empty() {
+                blocks.insert(next_block_id, block);
+                next_block_id += 1;
+            }
+        }
+
+        let mut worklist: Vec<(usize, char)> =
+            blocks.keys().flat_map(|&b| alphabet.iter().map(move |&c| (b, c))).collect();
+
+        while let Some((splitter_id, c)) = worklist.pop() {
+            let splitter = match blocks.get(&splitter_id) {
+                Some(block) => block.clone(),
+                None => continue, // this block was since absorbed into a split
+            };
+
+            let x: BTreeSet<Option<usize>> =
+                universe.iter().filter(|&&s| splitter.contains(&step(s, c))).cloned().collect();
+
+            for y_id in blocks.keys().cloned().collect::<Vec<_>>() {
+                let y = blocks[&y_id].clone();
+                let in_x: BTreeSet<_> = y.intersection(&x).cloned().collect();
+                let out_x: BTreeSet<_> = y.difference(&x).cloned().collect();
+
+                if in_x.is_empty() || out_x.is_empty() {
+                    continue; // the splitter doesn't divide this block
+                }
+
+                blocks.insert(y_id, in_x.clone());
+                let new_id = next_block_id;
+                next_block_id += 1;
+                blocks.insert(new_id, out_x.clone());
+
+                for &sym in &alphabet {
+                    match worklist.iter().position(|&(b, s)| b == y_id && s == sym) {
+                        Some(_) => worklist.push((new_id, sym)),
+                        None => {
+                            let smaller = if in_x.len() <= out_x.len() { y_id } else { new_id };
+                            worklist.push((smaller, sym));
+                        }
+                    }
+                }
+            }
+        }
+
+        // Block 0 must be the one containing the original start state.
+        let mut ordered: Vec<BTreeSet<Option<usize>>> = blocks.into_values().collect();
+        ordered.sort();
+        let start_block = ordered.iter().position(|b| b.contains(&Some(0))).unwrap_or(0);
+        ordered.swap(0, start_block);
+
+        let block_of = |s: Option<usize>| ordered.iter().position(|b| b.contains(&s)).expect("every state belongs to exactly one block");
+
+        let mut flat_delta = vec![];
+        let mut flat_f = vec![];
+        for (id, block) in ordered.iter().enumerate() {
+            if block.iter().any(|s| matches!(s, Some(s) if self.f.contains(s))) {
+                flat_f.push(id);
+            }
+
+            let representative = *block.iter().next().unwrap();
+            for &c in &alphabet {
+                if let Some(target) = step(representative, c) {
+                    flat_delta.push((id, c, block_of(Some(target))));
+                }
+            }
+        }
+
+        M::new(&flat_delta, &flat_f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(m: &mut M, s: &str) -> bool {
+        for c in s.chars() {
+            m.next(c);
+        }
+        let accepted = m.is_accepted();
+        m.reset();
+        accepted
+    }
+
+    #[test]
+    fn collapses_redundant_states_while_preserving_behavior() {
+        // An unnecessarily verbose automaton for "an even number of a's"
+        // (0 -a-> 1 -a-> 2 -a-> 1, where 0 and 2 are equivalent accepting
+        // states), which only needs 2 states.
+        let delta = [
+            (0, 'a', 1),
+            (1, 'a', 2),
+            (2, 'a', 1),
+        ];
+        let m = M::new(&delta, &[0, 2]);
+        let mut minimized = m.minimize();
+
+        let live_states: BTreeSet<usize> = minimized.delta.keys().map(|&(s, _)| s).collect();
+        assert_eq!(live_states.len(), 2);
+
+        assert!(run(&mut minimized, ""));
+        assert!(run(&mut minimized, "aa"));
+        assert!(run(&mut minimized, "aaaa"));
+        assert!(!run(&mut minimized, "a"));
+        assert!(!run(&mut minimized, "aaa"));
+    }
+
+    #[test]
+    fn minimizing_a_regex_compiled_automaton_keeps_it_equivalent() {
+        let mut original = M::from_regex("a*b+");
+        let mut minimized = original.minimize();
+
+        for s in ["b", "aaab", "aaabbb", "aaa", "", "ab"] {
+            assert_eq!(run(&mut original, s), run(&mut minimized, s), "mismatch on {:?}", s);
+        }
+    }
+}
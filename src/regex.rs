@@ -0,0 +1,401 @@
+//! Compiles a small regex dialect (concatenation, `|`, `*`, `+`, `?`,
+//! character classes like `[a-z]`, and `.`) down to the flat
+//! `(state, char, next_state)` transition form that [`M::new`] expects.
+//!
+//! The pipeline is the textbook one: parse the pattern into an [`Ast`],
+//! run Thompson construction to get an ε-NFA, then run subset
+//! construction to turn that into a DFA.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::M;
+
+#[derive(Debug, Clone)]
+enum Ast {
+    Empty,
+    Char(char),
+    Any,
+    Class(Vec<(char, char)>),
+    Concat(Box<Ast>, Box<Ast>),
+    Alt(Box<Ast>, Box<Ast>),
+    Star(Box<Ast>),
+}
+
+impl M {
+    /// Compiles `pattern` into a DFA via Thompson construction followed by
+    /// subset construction.
+    ///
+    /// `.` matches any character that appears literally (or inside a `[...]`
+    /// class) elsewhere in `pattern`; a pattern made up of nothing but `.`
+    /// has no alphabet to draw from and so matches nothing.
+    pub fn from_regex(pattern: &str) -> M {
+        let ast = parse(pattern);
+        let alphabet = alphabet_of(&ast);
+        let (flat_delta, flat_f) = compile(&ast, &alphabet);
+        M::new(&flat_delta, &flat_f)
+    }
+}
+
+// ==== Parsing ====
+//
+// Recursive descent over:
+//   alt    := concat ('|' concat)*
+//   concat := repeat*
+//   repeat := atom ('*' | '+' | '?')*
+//   atom   := '(' alt ')' | '[' class ']' | '.' | char
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().cloned()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        self.chars.next()
+    }
+
+    fn parse_alt(&mut self) -> Ast {
+        let mut node = self.parse_concat();
+        while self.peek() == Some('|') {
+            self.bump();
+            let rhs = self.parse_concat();
+            node = Ast::Alt(Box::new(node), Box::new(rhs));
+        }
+        node
+    }
+
+    fn parse_concat(&mut self) -> Ast {
+        let mut node = None;
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let rhs = self.parse_repeat();
+            node = Some(match node {
+                None => rhs,
+                Some(lhs) => Ast::Concat(Box::new(lhs), Box::new(rhs)),
+            });
+        }
+        node.unwrap_or(Ast::Empty)
+    }
+
+    fn parse_repeat(&mut self) -> Ast {
+        let mut node = self.parse_atom();
+        loop {
+            match self.peek() {
+                Some('*') => {
+                    self.bump();
+                    node = Ast::Star(Box::new(node));
+                }
+                Some('+') => {
+                    self.bump();
+                    node = Ast::Concat(Box::new(node.clone()), Box::new(Ast::Star(Box::new(node))));
+                }
+                Some('?') => {
+                    self.bump();
+                    node = Ast::Alt(Box::new(node), Box::new(Ast::Empty));
+                }
+                _ => break,
+            }
+        }
+        node
+    }
+
+    fn parse_atom(&mut self) -> Ast {
+        match self.bump() {
+            Some('(') => {
+                let node = self.parse_alt();
+                self.bump(); // ')'
+                node
+            }
+            Some('[') => self.parse_class(),
+            Some('.') => Ast::Any,
+            Some(c) => Ast::Char(c),
+            None => Ast::Empty,
+        }
+    }
+
+    fn parse_class(&mut self) -> Ast {
+        let mut ranges = vec![];
+        while let Some(c) = self.peek() {
+            if c == ']' {
+                break;
+            }
+            self.bump();
+            if self.peek() == Some('-') {
+                self.bump();
+                let hi = self.bump().unwrap_or(c);
+                ranges.push((c, hi));
+            } else {
+                ranges.push((c, c));
+            }
+        }
+        self.bump(); // ']'
+        Ast::Class(ranges)
+    }
+}
+
+fn parse(pattern: &str) -> Ast {
+    let mut parser = Parser { chars: pattern.chars().peekable() };
+    parser.parse_alt()
+}
+
+// ==== Alphabet ====
+
+fn alphabet_of(ast: &Ast) -> BTreeSet<char> {
+    let mut alphabet = BTreeSet::new();
+    collect_alphabet(ast, &mut alphabet);
+    alphabet
+}
+
+fn collect_alphabet(ast: &Ast, alphabet: &mut BTreeSet<char>) {
+    match ast {
+        Ast::Empty | Ast::Any => {}
+        Ast::Char(c) => {
+            alphabet.insert(*c);
+        }
+        Ast::Class(ranges) => {
+            for &(lo, hi) in ranges {
+                for c in lo..=hi {
+                    alphabet.insert(c);
+                }
+            }
+        }
+        Ast::Concat(a, b) | Ast::Alt(a, b) => {
+            collect_alphabet(a, alphabet);
+            collect_alphabet(b, alphabet);
+        }
+        Ast::Star(a) => collect_alphabet(a, alphabet),
+    }
+}
+
+// ==== Thompson construction ====
+//
+// Each transition is either a labeled move on a character or an ε-move.
+// Fragments are built with a dangling list of transition indices whose
+// target is patched in once the following fragment's start state is known.
+
+struct Fragment {
+    start: usize,
+    out: Vec<usize>,
+}
+
+struct Builder {
+    transitions: Vec<(usize, Option<char>, Option<usize>)>,
+    n_states: usize,
+}
+
+impl Builder {
+    fn new_state(&mut self) -> usize {
+        let state = self.n_states;
+        self.n_states += 1;
+        state
+    }
+
+    fn dangling(&mut self, from: usize, label: Option<char>) -> usize {
+        self.transitions.push((from, label, None));
+        self.transitions.len() - 1
+    }
+
+    fn edge(&mut self, from: usize, label: Option<char>, to: usize) {
+        self.transitions.push((from, label, Some(to)));
+    }
+
+    fn patch(&mut self, outs: &[usize], to: usize) {
+        for &i in outs {
+            self.transitions[i].2 = Some(to);
+        }
+    }
+
+    fn build(&mut self, ast: &Ast, alphabet: &BTreeSet<char>) -> Fragment {
+        match ast {
+            Ast::Empty => {
+                let start = self.new_state();
+                let out = self.dangling(start, None);
+                Fragment { start, out: vec![out] }
+            }
+            Ast::Char(c) => {
+                let start = self.new_state();
+                let out = self.dangling(start, Some(*c));
+                Fragment { start, out: vec![out] }
+            }
+            Ast::Any => {
+                let start = self.new_state();
+                let out = alphabet.iter().map(|&c| self.dangling(start, Some(c))).collect();
+                Fragment { start, out }
+            }
+            Ast::Class(ranges) => {
+                let start = self.new_state();
+                let mut chars = BTreeSet::new();
+                for &(lo, hi) in ranges {
+                    for c in lo..=hi {
+                        chars.insert(c);
+                    }
+                }
+                let out = chars.into_iter().map(|c| self.dangling(start, Some(c))).collect();
+                Fragment { start, out }
+            }
+            Ast::Concat(a, b) => {
+                let fa = self.build(a, alphabet);
+                let fb = self.build(b, alphabet);
+                self.patch(&fa.out, fb.start);
+                Fragment { start: fa.start, out: fb.out }
+            }
+            Ast::Alt(a, b) => {
+                let start = self.new_state();
+                let fa = self.build(a, alphabet);
+                let fb = self.build(b, alphabet);
+                self.edge(start, None, fa.start);
+                self.edge(start, None, fb.start);
+                let mut out = fa.out;
+                out.extend(fb.out);
+                Fragment { start, out }
+            }
+            Ast::Star(a) => {
+                let start = self.new_state();
+                let fa = self.build(a, alphabet);
+                self.edge(start, None, fa.start);
+                self.patch(&fa.out, start);
+                let out = self.dangling(start, None);
+                Fragment { start, out: vec![out] }
+            }
+        }
+    }
+
+    fn finish(self) -> Vec<(usize, Option<char>, usize)> {
+        self.transitions
+            .into_iter()
+            .map(|(from, label, to)| (from, label, to.expect("every fragment out-edge must be patched")))
+            .collect()
+    }
+}
+
+fn epsilon_closure(transitions: &[(usize, Option<char>, usize)], states: &BTreeSet<usize>) -> BTreeSet<usize> {
+    let mut closure = states.clone();
+    let mut stack: Vec<usize> = states.iter().cloned().collect();
+    while let Some(state) = stack.pop() {
+        for &(from, label, to) in transitions {
+            if from == state && label.is_none() && closure.insert(to) {
+                stack.push(to);
+            }
+        }
+    }
+    closure
+}
+
+// ==== Subset construction ====
+
+fn compile(ast: &Ast, alphabet: &BTreeSet<char>) -> (Vec<(usize, char, usize)>, Vec<usize>) {
+    let mut builder = Builder { transitions: vec![], n_states: 0 };
+    let frag = builder.build(ast, alphabet);
+    let nfa_accept = builder.new_state();
+    builder.patch(&frag.out, nfa_accept);
+    let transitions = builder.finish();
+
+    let start = epsilon_closure(&transitions, &vec![frag.start].into_iter().collect());
+
+    let mut ids: BTreeMap<BTreeSet<usize>, usize> = BTreeMap::new();
+    ids.insert(start.clone(), 0);
+    let mut queue = vec![start];
+    let mut flat_delta = vec![];
+
+    while let Some(set) = queue.pop() {
+        let id = ids[&set];
+        for &c in alphabet {
+            let moved: BTreeSet<usize> = set
+                .iter()
+                .flat_map(|&s| transitions.iter().filter(move |&&(from, label, _)| from == s && label == Some(c)))
+                .map(|&(_, _, to)| to)
+                .collect();
+
+            if moved.is_empty() {
+                continue;
+            }
+
+            let closure = epsilon_closure(&transitions, &moved);
+            let next_id = match ids.get(&closure) {
+                Some(&id) => id,
+                None => {
+                    let id = ids.len();
+                    ids.insert(closure.clone(), id);
+                    queue.push(closure);
+                    id
+                }
+            };
+            flat_delta.push((id, c, next_id));
+        }
+    }
+
+    let flat_f = ids
+        .iter()
+        .filter(|&(set, _)| set.contains(&nfa_accept))
+        .map(|(_, &id)| id)
+        .collect();
+
+    (flat_delta, flat_f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accepts(m: &mut M, s: &str) -> bool {
+        for c in s.chars() {
+            m.next(c);
+        }
+        let accepted = m.is_accepted();
+        m.reset();
+        accepted
+    }
+
+    #[test]
+    fn literal_concat() {
+        let mut m = M::from_regex("if");
+        assert!(accepts(&mut m, "if"));
+        assert!(!accepts(&mut m, "i"));
+        assert!(!accepts(&mut m, "iff"));
+    }
+
+    #[test]
+    fn alternation() {
+        let mut m = M::from_regex("if|else");
+        assert!(accepts(&mut m, "if"));
+        assert!(accepts(&mut m, "else"));
+        assert!(!accepts(&mut m, "elif"));
+    }
+
+    #[test]
+    fn star_and_plus() {
+        let mut m = M::from_regex("a*b+");
+        assert!(accepts(&mut m, "b"));
+        assert!(accepts(&mut m, "aaab"));
+        assert!(accepts(&mut m, "aaabbb"));
+        assert!(!accepts(&mut m, "aaa"));
+    }
+
+    #[test]
+    fn optional() {
+        let mut m = M::from_regex("colou?r");
+        assert!(accepts(&mut m, "color"));
+        assert!(accepts(&mut m, "colour"));
+        assert!(!accepts(&mut m, "colouur"));
+    }
+
+    #[test]
+    fn char_class_and_any() {
+        let mut m = M::from_regex("[a-z][a-z0-9]*");
+        assert!(accepts(&mut m, "x1"));
+        assert!(accepts(&mut m, "hello42"));
+        assert!(!accepts(&mut m, "1x"));
+
+        // `.` only ranges over characters that occur literally elsewhere in
+        // the pattern, so with no other letters around it matches 'a' or 'c'.
+        let mut any = M::from_regex("a.c");
+        assert!(accepts(&mut any, "aac"));
+        assert!(accepts(&mut any, "acc"));
+        assert!(!accepts(&mut any, "ac"));
+    }
+}
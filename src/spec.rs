@@ -0,0 +1,211 @@
+//! Parses a small text format for describing automata, so a machine can be
+//! defined in a data file instead of a Rust literal passed to [`M::new`].
+//!
+//! ```text
+//! STATES: q0 [q1] q2
+//! SYMBOLS: a b
+//! TRANSITIONS:
+//! q0, a, q1
+//! q0, b, q2
+//! q1, a | b, q1
+//! q2, *, q2
+//! FINAL: q1
+//! ```
+//!
+//! `STATES` lists every state name; the first one declared is the start
+//! state unless one is wrapped in `[brackets]`, in which case that one is
+//! used instead. Each `TRANSITIONS` line is `from, symbol, to`, where
+//! `symbol` may be a `|`-separated list to add several edges at once, or
+//! `*` to mean "every symbol in `SYMBOLS` not already given an explicit
+//! edge out of `from`".
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::M;
+
+enum Section {
+    None,
+    States,
+    Symbols,
+    Transitions,
+    Final,
+}
+
+fn intern(ids: &mut BTreeMap<String, usize>, name: &str) -> usize {
+    let next_id = ids.len();
+    *ids.entry(name.to_string()).or_insert(next_id)
+}
+
+fn tokens(line: &str) -> impl Iterator<Item = &str> {
+    line.split(|c: char| c == ',' || c.is_whitespace()).filter(|s| !s.is_empty())
+}
+
+fn parse_states(line: &str, ids: &mut BTreeMap<String, usize>, start: &mut usize) {
+    for token in tokens(line) {
+        if let Some(name) = token.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            *start = intern(ids, name);
+        } else {
+            intern(ids, token);
+        }
+    }
+}
+
+fn parse_symbols(line: &str, symbols: &mut BTreeSet<char>) {
+    for token in tokens(line) {
+        if let Some(c) = token.chars().next() {
+            symbols.insert(c);
+        }
+    }
+}
+
+fn parse_final(line: &str, ids: &mut BTreeMap<String, usize>, flat_f: &mut Vec<usize>) {
+    for token in tokens(line) {
+        flat_f.push(intern(ids, token));
+    }
+}
+
+fn parse_transition(
+    line: &str,
+    ids: &mut BTreeMap<String, usize>,
+    edges: &mut Vec<(usize, char, usize)>,
+    wildcards: &mut Vec<(usize, usize)>,
+) {
+    let parts: Vec<&str> = line.splitn(3, ',').map(|p| p.trim()).collect();
+    let (from_name, symbol_field, to_name) = match parts.as_slice() {
+        [from, symbol, to] => (*from, *symbol, *to),
+        _ => return, // malformed line, nothing sensible to record
+    };
+
+    let from = intern(ids, from_name);
+    let to = intern(ids, to_name);
+
+    if symbol_field == "*" {
+        wildcards.push((from, to));
+        return;
+    }
+
+    for alt in symbol_field.split('|') {
+        if let Some(c) = alt.trim().chars().next() {
+            edges.push((from, c, to));
+        }
+    }
+}
+
+impl M {
+    /// Builds an automaton from the text format described in this module's
+    /// docs.
+    pub fn from_spec(text: &str) -> M {
+        let mut ids: BTreeMap<String, usize> = BTreeMap::new();
+        let mut start = 0;
+        let mut symbols: BTreeSet<char> = BTreeSet::new();
+        let mut edges: Vec<(usize, char, usize)> = vec![];
+        let mut wildcards: Vec<(usize, usize)> = vec![];
+        let mut flat_f: Vec<usize> = vec![];
+        let mut section = Section::None;
+
+        for raw_line in text.lines() {
+            let line = raw_line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(rest) = line.strip_prefix("STATES:") {
+                section = Section::States;
+                parse_states(rest, &mut ids, &mut start);
+            } else if let Some(rest) = line.strip_prefix("SYMBOLS:") {
+                section = Section::Symbols;
+                parse_symbols(rest, &mut symbols);
+            } else if line.strip_prefix("TRANSITIONS:").is_some() {
+                section = Section::Transitions;
+            } else if let Some(rest) = line.strip_prefix("FINAL:") {
+                section = Section::Final;
+                parse_final(rest, &mut ids, &mut flat_f);
+            } else {
+                match section {
+                    Section::States => parse_states(line, &mut ids, &mut start),
+                    Section::Symbols => parse_symbols(line, &mut symbols),
+                    Section::Transitions => parse_transition(line, &mut ids, &mut edges, &mut wildcards),
+                    Section::Final => parse_final(line, &mut ids, &mut flat_f),
+                    Section::None => {}
+                }
+            }
+        }
+
+        let mut covered: BTreeMap<usize, BTreeSet<char>> = BTreeMap::new();
+        for &(from, c, _) in &edges {
+            covered.entry(from).or_default().insert(c);
+        }
+        for (from, to) in wildcards {
+            let already = covered.entry(from).or_default();
+            for &c in &symbols {
+                if already.insert(c) {
+                    edges.push((from, c, to));
+                }
+            }
+        }
+
+        // `M` always starts execution at state 0, so swap ids to put the
+        // declared start state there.
+        if start != 0 {
+            let swap = |s: usize| if s == 0 { start } else if s == start { 0 } else { s };
+            for edge in edges.iter_mut() {
+                edge.0 = swap(edge.0);
+                edge.2 = swap(edge.2);
+            }
+            for final_state in flat_f.iter_mut() {
+                *final_state = swap(*final_state);
+            }
+        }
+
+        M::new(&edges, &flat_f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(m: &mut M, s: &str) -> bool {
+        for c in s.chars() {
+            m.next(c);
+        }
+        let accepted = m.is_accepted();
+        m.reset();
+        accepted
+    }
+
+    #[test]
+    fn builds_a_dfa_from_a_spec() {
+        let spec = "
+            STATES: [q0] q1 q2
+            SYMBOLS: a b
+            TRANSITIONS:
+            q0, a, q1
+            q0, b, q2
+            q1, a | b, q1
+            q2, *, q2
+            FINAL: q1
+        ";
+
+        let mut m = M::from_spec(spec);
+        assert!(!run(&mut m, ""));
+        assert!(run(&mut m, "ab"));
+        assert!(!run(&mut m, "b"));
+        assert!(!run(&mut m, "ba"));
+    }
+
+    #[test]
+    fn first_declared_state_is_the_default_start() {
+        let spec = "
+            STATES: s0 s1
+            SYMBOLS: a
+            TRANSITIONS:
+            s0, a, s1
+            FINAL: s1
+        ";
+
+        let mut m = M::from_spec(spec);
+        assert!(run(&mut m, "a"));
+        assert!(!run(&mut m, ""));
+    }
+}